@@ -2,9 +2,21 @@ use anyhow::Result;
 use spirv_builder::{MetadataPrintout, SpirvBuilder};
 
 fn main() -> Result<()> {
+    // `print_metadata(Full)` already walks shaders/src and emits its own
+    // cargo:rerun-if-changed lines per compiled file, but this one covers
+    // the crate root too (Cargo.toml changes, new files not yet picked up).
     println!("cargo:rerun-if-changed=shaders/src");
-    // SpirvBuilder::new("shaders", "spirv-unknown-vulkan1.2")
-    //     .print_metadata(MetadataPrintout::Full)
-    //     .build()?;
+    println!("cargo:rerun-if-changed=shaders/Cargo.toml");
+
+    let result = SpirvBuilder::new("shaders", "spirv-unknown-vulkan1.2")
+        .print_metadata(MetadataPrintout::Full)
+        .build()?;
+
+    // Forward the compiled module path and its entry points as env vars so
+    // the `shader` module can load the SPIR-V cargo actually produced,
+    // rather than a hardcoded filename that drifts out of sync.
+    println!("cargo:rustc-env=RTRT_SHADER_SPV_PATH={}", result.module.unwrap_single().display());
+    println!("cargo:rustc-env=RTRT_SHADER_ENTRY_POINTS={}", result.entry_points.join(","));
+
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,166 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use anyhow::Result;
+use ash::vk::{self, DeviceMemory, DeviceSize, MemoryPropertyFlags, MemoryRequirements};
+
+use super::device::DeviceCtx;
+
+/// Vulkan implementations cap `maxMemoryAllocationCount`, so we can't afford
+/// one `vkAllocateMemory` per buffer. Instead we allocate memory in blocks of
+/// this size per memory-type-index and carve out aligned sub-regions.
+const BLOCK_SIZE: DeviceSize = 64 * 1024 * 1024;
+
+struct MemoryBlock {
+    memory: DeviceMemory,
+    size: DeviceSize,
+    /// Bump cursor into the block. This allocator never frees sub-regions;
+    /// buffers are expected to live for the lifetime of the block they came
+    /// from (true for the write-once vertex/index/storage buffers this
+    /// exists for), not to be created and dropped in a tight loop - a
+    /// transient buffer's space is gone for good once dropped.
+    cursor: DeviceSize,
+    /// A `VkDeviceMemory` must not be mapped more than once simultaneously
+    /// (VUID-vkMapMemory-memory-00678), but many buffers can share a block,
+    /// so the block - not each buffer - owns the one live mapping. `map_block`/
+    /// `unmap_block` ref-count it via `map_count`.
+    mapped_ptr: Option<*mut u8>,
+    map_count: usize
+}
+
+/// A sub-region of a larger `vkDeviceMemory` allocation, as handed out by
+/// [`MemoryAllocatorCtx::allocate`].
+#[derive(Clone, Copy)]
+pub struct MemoryAllocation {
+    pub memory: DeviceMemory,
+    pub offset: DeviceSize,
+    pub size: DeviceSize
+}
+
+pub(crate) fn align_up(value: DeviceSize, alignment: DeviceSize) -> DeviceSize {
+    if alignment == 0 { value } else { (value + alignment - 1) & !(alignment - 1) }
+}
+
+pub(crate) fn align_down(value: DeviceSize, alignment: DeviceSize) -> DeviceSize {
+    if alignment == 0 { value } else { value & !(alignment - 1) }
+}
+
+/// Sub-allocates device memory blocks per memory-type-index so that many
+/// buffers can share a small number of underlying `vkAllocateMemory` calls.
+pub struct MemoryAllocatorCtx {
+    device_ctx: Rc<DeviceCtx>,
+    blocks: RefCell<HashMap<u32, Vec<MemoryBlock>>>
+}
+
+impl MemoryAllocatorCtx {
+    pub fn new(device_ctx: Rc<DeviceCtx>) -> MemoryAllocatorCtx {
+        MemoryAllocatorCtx {
+            device_ctx,
+            blocks: RefCell::new(HashMap::new())
+        }
+    }
+
+    /// Scans `vkGetPhysicalDeviceMemoryProperties` for a memory type whose
+    /// `memoryTypeBits` matches `requirements` and whose property flags
+    /// contain `properties` (e.g. `DEVICE_LOCAL`, or
+    /// `HOST_VISIBLE | HOST_COHERENT` for staging buffers).
+    fn find_memory_type_index(&self, requirements: MemoryRequirements, properties: MemoryPropertyFlags) -> Result<u32> {
+        let memory_props = unsafe {
+            self.device_ctx.surface_ctx.instance_ctx.instance.get_physical_device_memory_properties(self.device_ctx.physical_info.device)
+        };
+        (0..memory_props.memory_type_count)
+            .find(|&index| {
+                let type_supported = requirements.memory_type_bits & (1 << index) != 0;
+                let properties_supported = memory_props.memory_types[index as usize].property_flags.contains(properties);
+                type_supported && properties_supported
+            })
+            .ok_or(anyhow::anyhow!("No suitable memory type for requirements {requirements:?} with properties {properties:?}"))
+    }
+
+    pub fn allocate(&self, requirements: MemoryRequirements, properties: MemoryPropertyFlags) -> Result<MemoryAllocation> {
+        let type_index = self.find_memory_type_index(requirements, properties)?;
+
+        // Host-visible sub-regions are over-aligned and padded up to the
+        // atom size so that a `vkFlushMappedMemoryRanges`/
+        // `vkInvalidateMappedMemoryRanges` call aligned to it for one
+        // buffer's range can never overlap a neighbouring buffer's region
+        // (VUID-VkMappedMemoryRange-offset-00794 / size-01390).
+        let atom_size = if properties.contains(MemoryPropertyFlags::HOST_VISIBLE) {
+            self.device_ctx.non_coherent_atom_size().max(1)
+        } else {
+            1
+        };
+        let alignment = requirements.alignment.max(atom_size);
+        let padded_size = align_up(requirements.size, atom_size);
+
+        let mut blocks_by_type = self.blocks.borrow_mut();
+        let blocks = blocks_by_type.entry(type_index).or_default();
+
+        if let Some(block) = blocks.iter_mut().find(|block| {
+            let offset = align_up(block.cursor, alignment);
+            offset + padded_size <= block.size
+        }) {
+            let offset = align_up(block.cursor, alignment);
+            block.cursor = offset + padded_size;
+            return Ok(MemoryAllocation { memory: block.memory, offset, size: requirements.size });
+        }
+
+        let block_size = BLOCK_SIZE.max(padded_size);
+        let allocate_info = vk::MemoryAllocateInfo::builder()
+            .allocation_size(block_size)
+            .memory_type_index(type_index)
+            .build();
+        let memory = unsafe { self.device_ctx.logical_info.device.allocate_memory(&allocate_info, None)? };
+        self.device_ctx.debug_namer.name_object(memory, &format!("rtrt::memory_block[type={type_index}]"))?;
+
+        blocks.push(MemoryBlock { memory, size: block_size, cursor: padded_size, mapped_ptr: None, map_count: 0 });
+        log::debug!("MemoryAllocatorCtx allocated new {block_size} byte block for memory type {type_index}");
+        Ok(MemoryAllocation { memory, offset: 0, size: requirements.size })
+    }
+
+    /// Maps the whole block backing `memory` (not just one buffer's
+    /// sub-region) the first time it's requested, and hands back the same
+    /// pointer to every subsequent caller until the last matching
+    /// `unmap_block` call. This is what lets two host-visible buffers that
+    /// share a block both be "mapped" at once without a second
+    /// `vkMapMemory` on the same `VkDeviceMemory`.
+    pub fn map_block(&self, memory: DeviceMemory) -> Result<*mut u8> {
+        let mut blocks_by_type = self.blocks.borrow_mut();
+        let block = blocks_by_type.values_mut()
+            .flatten()
+            .find(|block| block.memory == memory)
+            .ok_or_else(|| anyhow::anyhow!("map_block: memory not owned by this allocator"))?;
+
+        if block.map_count == 0 {
+            let ptr = unsafe { self.device_ctx.logical_info.device.map_memory(memory, 0, vk::WHOLE_SIZE, vk::MemoryMapFlags::empty())? };
+            block.mapped_ptr = Some(ptr as *mut u8);
+        }
+        block.map_count += 1;
+        Ok(block.mapped_ptr.expect("block.mapped_ptr set above when map_count was 0"))
+    }
+
+    pub fn unmap_block(&self, memory: DeviceMemory) {
+        let mut blocks_by_type = self.blocks.borrow_mut();
+        let Some(block) = blocks_by_type.values_mut().flatten().find(|block| block.memory == memory) else { return; };
+        block.map_count = block.map_count.saturating_sub(1);
+        if block.map_count == 0 {
+            unsafe { self.device_ctx.logical_info.device.unmap_memory(memory); }
+            block.mapped_ptr = None;
+        }
+    }
+}
+
+impl Drop for MemoryAllocatorCtx {
+    fn drop(&mut self) {
+        unsafe {
+            for blocks in self.blocks.get_mut().values() {
+                for block in blocks {
+                    if block.map_count > 0 {
+                        self.device_ctx.logical_info.device.unmap_memory(block.memory);
+                    }
+                    self.device_ctx.logical_info.device.free_memory(block.memory, None);
+                }
+            }
+        }
+        log::debug!("MemoryAllocatorCtx dropped");
+    }
+}
@@ -0,0 +1,121 @@
+use std::rc::Rc;
+
+use anyhow::Result;
+use ash::vk::{self, Buffer, BufferUsageFlags, DeviceSize, MemoryPropertyFlags};
+
+use super::{allocator::{align_down, align_up, MemoryAllocation, MemoryAllocatorCtx}, command_pool::CommandPoolCtx, device::DeviceCtx};
+
+pub struct BufferCtx {
+    pub device_ctx: Rc<DeviceCtx>,
+    allocator: Rc<MemoryAllocatorCtx>,
+    pub buffer: Buffer,
+    pub allocation: MemoryAllocation,
+    pub size: DeviceSize,
+    properties: MemoryPropertyFlags
+}
+
+impl BufferCtx {
+    pub fn new(
+        device_ctx: Rc<DeviceCtx>,
+        allocator: Rc<MemoryAllocatorCtx>,
+        size: DeviceSize,
+        usage: BufferUsageFlags,
+        properties: MemoryPropertyFlags
+    ) -> Result<Rc<BufferCtx>> {
+        let buffer_info = vk::BufferCreateInfo::builder()
+            .size(size)
+            .usage(usage)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .build();
+        let buffer = unsafe { device_ctx.logical_info.device.create_buffer(&buffer_info, None)? };
+        device_ctx.debug_namer.name_object(buffer, "rtrt::buffer")?;
+
+        let requirements = unsafe { device_ctx.logical_info.device.get_buffer_memory_requirements(buffer) };
+        let allocation = allocator.allocate(requirements, properties)?;
+        unsafe { device_ctx.logical_info.device.bind_buffer_memory(buffer, allocation.memory, allocation.offset)?; }
+
+        log::debug!("BufferCtx created ({size} bytes, {usage:?})");
+        Ok(Rc::new(BufferCtx {
+            device_ctx,
+            allocator,
+            buffer,
+            allocation,
+            size,
+            properties
+        }))
+    }
+
+    /// Maps this buffer's sub-region and returns a pointer to its start.
+    /// Only valid for buffers allocated with `HOST_VISIBLE` memory.
+    ///
+    /// Many buffers can share one underlying `VkDeviceMemory` block (see
+    /// [`MemoryAllocatorCtx`]), and Vulkan forbids mapping the same
+    /// `VkDeviceMemory` more than once simultaneously
+    /// (VUID-vkMapMemory-memory-00678). This goes through
+    /// [`MemoryAllocatorCtx::map_block`] rather than calling `vkMapMemory`
+    /// directly so that two buffers in the same block can both be "mapped"
+    /// at once - the block is mapped once and ref-counted, and every buffer
+    /// gets a pointer offset into it.
+    pub fn map(&self) -> Result<*mut u8> {
+        anyhow::ensure!(self.properties.contains(MemoryPropertyFlags::HOST_VISIBLE), "buffer is not host-visible");
+        let block_ptr = self.allocator.map_block(self.allocation.memory)?;
+        Ok(unsafe { block_ptr.add(self.allocation.offset as usize) })
+    }
+
+    pub fn unmap(&self) {
+        self.allocator.unmap_block(self.allocation.memory);
+    }
+
+    /// Maps, copies `data` in, and unmaps. Requires `HOST_VISIBLE` memory;
+    /// if the memory isn't also `HOST_COHERENT`, flushes the written range
+    /// with `vkFlushMappedMemoryRanges` so the GPU is guaranteed to see it.
+    pub fn copy_from_slice<T: Copy>(&self, data: &[T]) -> Result<()> {
+        let byte_len = std::mem::size_of_val(data) as DeviceSize;
+        anyhow::ensure!(byte_len <= self.size, "data ({byte_len} bytes) does not fit in buffer ({} bytes)", self.size);
+        let ptr = self.map()?;
+        unsafe { std::ptr::copy_nonoverlapping(data.as_ptr() as *const u8, ptr, byte_len as usize); }
+        if !self.properties.contains(MemoryPropertyFlags::HOST_COHERENT) {
+            // `offset`/`size` must be aligned to `nonCoherentAtomSize`
+            // (VUID-VkMappedMemoryRange-offset-00794 / size-01390).
+            // `MemoryAllocatorCtx` already over-aligns and pads host-visible
+            // sub-regions to this same granularity, so rounding here can
+            // only ever widen the range to the edges of our own region, not
+            // into a neighbouring buffer's.
+            let atom_size = self.device_ctx.non_coherent_atom_size().max(1);
+            let offset = align_down(self.allocation.offset, atom_size);
+            let size = align_up(self.allocation.offset - offset + self.size, atom_size);
+            let range = vk::MappedMemoryRange::builder()
+                .memory(self.allocation.memory)
+                .offset(offset)
+                .size(size)
+                .build();
+            unsafe { self.device_ctx.logical_info.device.flush_mapped_memory_ranges(&[range])?; }
+        }
+        self.unmap();
+        Ok(())
+    }
+
+    /// Records a one-time `vkCmdCopyBuffer` from `self` into `dst`, for
+    /// moving data out of a host-visible staging buffer into device-local
+    /// memory.
+    pub fn copy_to(&self, dst: &BufferCtx, command_pool_ctx: &CommandPoolCtx, queue: vk::Queue) -> Result<()> {
+        command_pool_ctx.run_one_time_commands(queue, |command_buffer| {
+            let region = vk::BufferCopy::builder()
+                .size(self.size.min(dst.size))
+                .build();
+            unsafe {
+                self.device_ctx.logical_info.device.cmd_copy_buffer(command_buffer, self.buffer, dst.buffer, &[region]);
+            }
+            Ok(())
+        })
+    }
+}
+
+impl Drop for BufferCtx {
+    fn drop(&mut self) {
+        unsafe {
+            self.device_ctx.logical_info.device.destroy_buffer(self.buffer, None);
+        }
+        log::debug!("BufferCtx dropped");
+    }
+}
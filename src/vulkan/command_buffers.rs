@@ -0,0 +1,46 @@
+use std::rc::Rc;
+
+use anyhow::Result;
+use ash::vk::{self, CommandBuffer};
+
+use super::command_pool::CommandPoolCtx;
+
+/// A set of primary command buffers allocated from a single pool, one per
+/// frame-in-flight (or per swapchain image), so each frame records into its
+/// own buffer without contending with the others.
+pub struct CommandBuffersCtx {
+    pub command_pool_ctx: Rc<CommandPoolCtx>,
+    pub command_buffers: Vec<CommandBuffer>
+}
+
+impl CommandBuffersCtx {
+    pub fn new(
+        command_pool_ctx: Rc<CommandPoolCtx>,
+        count: u32
+    ) -> Result<Rc<CommandBuffersCtx>> {
+        let alloc_info = vk::CommandBufferAllocateInfo::builder()
+            .command_pool(command_pool_ctx.command_pool)
+            .level(vk::CommandBufferLevel::PRIMARY)
+            .command_buffer_count(count)
+            .build();
+        let command_buffers = unsafe { command_pool_ctx.device_ctx.logical_info.device.allocate_command_buffers(&alloc_info)? };
+        for (index, &command_buffer) in command_buffers.iter().enumerate() {
+            command_pool_ctx.device_ctx.debug_namer.name_object(command_buffer, &format!("rtrt::command_buffer[{index}]"))?;
+        }
+
+        log::debug!("CommandBuffersCtx created ({count} buffers)");
+        Ok(Rc::new(CommandBuffersCtx {
+            command_pool_ctx,
+            command_buffers
+        }))
+    }
+}
+
+impl Drop for CommandBuffersCtx {
+    fn drop(&mut self) {
+        unsafe {
+            self.command_pool_ctx.device_ctx.logical_info.device.free_command_buffers(self.command_pool_ctx.command_pool, &self.command_buffers);
+        }
+        log::debug!("CommandBuffersCtx dropped");
+    }
+}
@@ -0,0 +1,77 @@
+use std::rc::Rc;
+
+use anyhow::Result;
+use ash::vk::{self, CommandBuffer, CommandPool, Queue};
+
+use super::device::DeviceCtx;
+
+pub struct CommandPoolCtx {
+    pub device_ctx: Rc<DeviceCtx>,
+    pub command_pool: CommandPool
+}
+
+impl CommandPoolCtx {
+    pub fn new(
+        device_ctx: Rc<DeviceCtx>,
+        queue_family_index: u32,
+        flags: vk::CommandPoolCreateFlags
+    ) -> Result<Rc<CommandPoolCtx>> {
+        let pool_info = vk::CommandPoolCreateInfo::builder()
+            .queue_family_index(queue_family_index)
+            .flags(flags)
+            .build();
+        let command_pool = unsafe { device_ctx.logical_info.device.create_command_pool(&pool_info, None)? };
+        device_ctx.debug_namer.name_object(command_pool, "rtrt::command_pool")?;
+
+        log::debug!("CommandPoolCtx created");
+        Ok(Rc::new(CommandPoolCtx {
+            device_ctx,
+            command_pool
+        }))
+    }
+
+    /// Allocates a transient command buffer, records `record` into it, then
+    /// submits it to `queue` and waits for it to finish before freeing it.
+    /// Intended for infrequent, one-off GPU work such as a staging buffer
+    /// copy, not for per-frame recording.
+    pub fn run_one_time_commands(
+        &self,
+        queue: Queue,
+        record: impl FnOnce(CommandBuffer) -> Result<()>
+    ) -> Result<()> {
+        let device = &self.device_ctx.logical_info.device;
+        let alloc_info = vk::CommandBufferAllocateInfo::builder()
+            .command_pool(self.command_pool)
+            .level(vk::CommandBufferLevel::PRIMARY)
+            .command_buffer_count(1)
+            .build();
+        let command_buffer = unsafe { device.allocate_command_buffers(&alloc_info)? }[0];
+
+        let begin_info = vk::CommandBufferBeginInfo::builder()
+            .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT)
+            .build();
+        unsafe { device.begin_command_buffer(command_buffer, &begin_info)?; }
+        record(command_buffer)?;
+        unsafe { device.end_command_buffer(command_buffer)?; }
+
+        let command_buffers = [command_buffer];
+        let submit_info = vk::SubmitInfo::builder()
+            .command_buffers(&command_buffers)
+            .build();
+        unsafe {
+            device.queue_submit(queue, &[submit_info], vk::Fence::null())?;
+            device.queue_wait_idle(queue)?;
+            device.free_command_buffers(self.command_pool, &command_buffers);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for CommandPoolCtx {
+    fn drop(&mut self) {
+        unsafe {
+            self.device_ctx.logical_info.device.destroy_command_pool(self.command_pool, None);
+        }
+        log::debug!("CommandPoolCtx dropped");
+    }
+}
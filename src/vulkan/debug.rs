@@ -0,0 +1,125 @@
+use std::{
+    borrow::Cow,
+    ffi::{CStr, CString, c_void}
+};
+
+use anyhow::Result;
+use ash::{extensions::ext::DebugUtils, vk::{self, CommandBuffer, Handle}, Device, Entry, Instance};
+
+/// Receives `vkCreateDebugUtilsMessengerEXT` callbacks and logs them with
+/// human-readable severity/type tags, so the object names [`DebugNamer`]
+/// attaches actually show up somewhere. Instance-level, since the messenger
+/// is registered against the instance rather than a logical device.
+pub struct DebugMessengerCtx {
+    debug_utils_loader: DebugUtils,
+    messenger: vk::DebugUtilsMessengerEXT
+}
+
+impl DebugMessengerCtx {
+    pub fn new(entry: &Entry, instance: &Instance) -> Result<DebugMessengerCtx> {
+        let debug_utils_loader = DebugUtils::new(entry, instance);
+        let messenger_info = vk::DebugUtilsMessengerCreateInfoEXT::builder()
+            .message_severity(
+                vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE
+                    | vk::DebugUtilsMessageSeverityFlagsEXT::INFO
+                    | vk::DebugUtilsMessageSeverityFlagsEXT::WARNING
+                    | vk::DebugUtilsMessageSeverityFlagsEXT::ERROR
+            )
+            .message_type(
+                vk::DebugUtilsMessageTypeFlagsEXT::GENERAL
+                    | vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION
+                    | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE
+            )
+            .pfn_user_callback(Some(debug_callback))
+            .build();
+        let messenger = unsafe { debug_utils_loader.create_debug_utils_messenger(&messenger_info, None)? };
+
+        log::debug!("DebugMessengerCtx created");
+        Ok(DebugMessengerCtx {
+            debug_utils_loader,
+            messenger
+        })
+    }
+}
+
+impl Drop for DebugMessengerCtx {
+    fn drop(&mut self) {
+        unsafe {
+            self.debug_utils_loader.destroy_debug_utils_messenger(self.messenger, None);
+        }
+        log::debug!("DebugMessengerCtx dropped");
+    }
+}
+
+unsafe extern "system" fn debug_callback(
+    message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+    message_type: vk::DebugUtilsMessageTypeFlagsEXT,
+    callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT,
+    _user_data: *mut c_void
+) -> vk::Bool32 {
+    let callback_data = unsafe { *callback_data };
+    let message = if callback_data.p_message.is_null() {
+        Cow::Borrowed("<no message>")
+    } else {
+        unsafe { CStr::from_ptr(callback_data.p_message) }.to_string_lossy()
+    };
+
+    let log_line = format!("[{message_type:?}] {message}");
+    match message_severity {
+        vk::DebugUtilsMessageSeverityFlagsEXT::ERROR => log::error!("{log_line}"),
+        vk::DebugUtilsMessageSeverityFlagsEXT::WARNING => log::warn!("{log_line}"),
+        vk::DebugUtilsMessageSeverityFlagsEXT::INFO => log::info!("{log_line}"),
+        _ => log::debug!("{log_line}")
+    }
+
+    vk::FALSE
+}
+
+/// Wraps `VK_EXT_debug_utils` object naming and command buffer markers so
+/// the messages the callback above logs reference something more useful
+/// than a raw handle. Disabled (a no-op) whenever validation layers are
+/// disabled, so release builds pay nothing for it.
+pub struct DebugNamer {
+    debug_utils_loader: Option<DebugUtils>,
+    device: Device
+}
+
+impl DebugNamer {
+    pub fn new(entry: &Entry, instance: &Instance, device: &Device, enable_validation: bool) -> DebugNamer {
+        let debug_utils_loader = enable_validation.then(|| DebugUtils::new(entry, instance));
+        DebugNamer {
+            debug_utils_loader,
+            device: device.clone()
+        }
+    }
+
+    /// Names a Vulkan object, e.g. `namer.name_object(render_pass, "rtrt::render_pass")`.
+    pub fn name_object<T: Handle>(&self, handle: T, name: &str) -> Result<()> {
+        let Some(debug_utils_loader) = &self.debug_utils_loader else { return Ok(()); };
+        let name = CString::new(name)?;
+        let name_info = vk::DebugUtilsObjectNameInfoEXT::builder()
+            .object_type(T::TYPE)
+            .object_handle(handle.as_raw())
+            .object_name(&name)
+            .build();
+        unsafe { debug_utils_loader.set_debug_utils_object_name(self.device.handle(), &name_info)?; }
+        Ok(())
+    }
+
+    /// Begins a named, coloured debug label on a command buffer, e.g. to
+    /// bracket a render pass in a GPU capture tool.
+    pub fn begin_label(&self, command_buffer: CommandBuffer, label: &str, color: [f32; 4]) {
+        let Some(debug_utils_loader) = &self.debug_utils_loader else { return; };
+        let Ok(label_name) = CString::new(label) else { return; };
+        let label_info = vk::DebugUtilsLabelEXT::builder()
+            .label_name(&label_name)
+            .color(color)
+            .build();
+        unsafe { debug_utils_loader.cmd_begin_debug_utils_label(command_buffer, &label_info); }
+    }
+
+    pub fn end_label(&self, command_buffer: CommandBuffer) {
+        let Some(debug_utils_loader) = &self.debug_utils_loader else { return; };
+        unsafe { debug_utils_loader.cmd_end_debug_utils_label(command_buffer); }
+    }
+}
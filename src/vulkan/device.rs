@@ -1,15 +1,30 @@
 use std::{ffi::{CStr, c_char, CString}, rc::Rc};
 use anyhow::Result;
-use ash::{vk::{self, PhysicalDevice, Queue, PhysicalDeviceVulkanMemoryModelFeatures, SurfaceCapabilitiesKHR, SurfaceFormatKHR, PresentModeKHR}, Device, extensions::khr::Swapchain};
+use ash::{vk::{self, PhysicalDevice, PhysicalDeviceType, Queue, PhysicalDeviceVulkanMemoryModelFeatures, SurfaceCapabilitiesKHR, SurfaceFormatKHR, PresentModeKHR}, Device, extensions::khr::Swapchain};
 
-use super::surface::SurfaceCtx;
+use super::{debug::DebugNamer, surface::SurfaceCtx};
 
-fn get_required_device_extensions() -> Vec<CString> {
+/// Always required by `rtrt` in addition to whatever a caller requests, since
+/// there is no code path that does not need to present to the surface.
+fn base_required_device_extensions() -> Vec<CString> {
     vec![
         Swapchain::name().to_owned()
     ]
 }
 
+/// The ray-tracing extensions `rtrt` wants but can run without, so they are
+/// passed to [`DeviceCtx::new`] as optional rather than required extensions.
+/// Hardware that lacks them still gets a working rasterised swapchain; code
+/// that wants to do ray tracing should check
+/// [`PhysicalDeviceInfo::supports_extension`] before using it.
+pub fn ray_tracing_device_extensions() -> Vec<CString> {
+    vec![
+        vk::KhrAccelerationStructureFn::name().to_owned(),
+        vk::KhrRayTracingPipelineFn::name().to_owned(),
+        vk::KhrDeferredHostOperationsFn::name().to_owned()
+    ]
+}
+
 fn current_swapchain_support_impl(
     surface_ctx: &SurfaceCtx,
     physical_device: PhysicalDevice
@@ -24,9 +39,26 @@ fn current_swapchain_support_impl(
     })
 }
 
+/// Score a candidate device so that multi-GPU systems (e.g. laptops with an
+/// integrated + discrete GPU) prefer the discrete GPU, while still letting
+/// supported optional extensions (ray tracing, ...) nudge the outcome.
+fn score_physical_device(
+    props: &vk::PhysicalDeviceProperties,
+    supported_optional_extensions: &[CString]
+) -> u32 {
+    let mut score = 0u32;
+    if props.device_type == PhysicalDeviceType::DISCRETE_GPU {
+        score += 10_000;
+    }
+    score += props.limits.max_image_dimension2_d;
+    score += supported_optional_extensions.len() as u32 * 1_000;
+    score
+}
+
 fn select_physical_device(
     surface_ctx: &SurfaceCtx,
-    required_device_extensions: &[&CStr]
+    required_device_extensions: &[&CStr],
+    optional_device_extensions: &[&CStr]
 ) -> Result<PhysicalDeviceInfo> {
     let devices = unsafe { surface_ctx.instance_ctx.instance.enumerate_physical_devices() }?;
     let devices_and_queues = devices.into_iter()
@@ -35,36 +67,43 @@ fn select_physical_device(
     devices_and_queues.into_iter()
     .filter_map(|(device, queues)| {
         let (graphics_family_index, present_family_index) = queues?;
-        let supports_required_extensions = test_required_extensions(surface_ctx, device, required_device_extensions).ok()?;
+        let supported_extensions = list_supported_extensions(surface_ctx, device).ok()?;
+        let is_supported = |name: &CStr| supported_extensions.iter().any(|x| x.as_c_str() == name);
+        let supports_required_extensions = required_device_extensions.iter().all(|x| is_supported(x));
         if !supports_required_extensions { return None; }
         let swapchain_support_details = current_swapchain_support_impl(surface_ctx, device).ok()?;
         let swapchain_is_adequate = !swapchain_support_details.formats.is_empty() && !swapchain_support_details.present_modes.is_empty();
         if !swapchain_is_adequate { return None; }
+        let supported_optional_extensions = optional_device_extensions.iter()
+            .filter(|x| is_supported(x))
+            .map(|x| x.to_owned().to_owned())
+            .collect::<Vec<_>>();
         let props = unsafe { surface_ctx.instance_ctx.instance.get_physical_device_properties(device) };
         let debug_device_name = unsafe { CStr::from_ptr(props.device_name.as_ptr()) }.to_owned();
         let dedup_family_indices = if graphics_family_index == present_family_index { vec![graphics_family_index] } else { vec![graphics_family_index, present_family_index] };
-        Some(PhysicalDeviceInfo {
+        let score = score_physical_device(&props, &supported_optional_extensions);
+        Some((score, PhysicalDeviceInfo {
             device,
             graphics_family_index,
             present_family_index,
             dedup_family_indices,
             debug_device_name,
-        })
+            supported_optional_extensions,
+        }))
     })
-    .next().ok_or(anyhow::anyhow!("No suitable physical device"))
+    .max_by_key(|(score, _)| *score)
+    .map(|(_, info)| info)
+    .ok_or(anyhow::anyhow!("No suitable physical device"))
 }
 
-fn test_required_extensions(
+fn list_supported_extensions(
     surface_ctx: &SurfaceCtx,
-    device: PhysicalDevice,
-    required_device_extensions: &[&CStr]
-) -> Result<bool> {
+    device: PhysicalDevice
+) -> Result<Vec<CString>> {
     let extension_props = unsafe { surface_ctx.instance_ctx.instance.enumerate_device_extension_properties(device)? };
-    let extension_names = extension_props.iter()
-        .map(|x| unsafe { CStr::from_ptr(x.extension_name.as_ptr()) })
-        .collect::<Vec<_>>();
-    let has_all_extensions = required_device_extensions.iter().all(|x| extension_names.contains(x));
-    Ok(has_all_extensions)
+    Ok(extension_props.iter()
+        .map(|x| unsafe { CStr::from_ptr(x.extension_name.as_ptr()) }.to_owned())
+        .collect())
 }
 
 fn find_queue_families(
@@ -105,7 +144,9 @@ fn create_logical_device(
             .queue_priorities(&queue_priorities)
             .build()
         ).collect::<Vec<_>>();
-    let device_extensions_ptrs = required_device_extensions.iter().map(|x| x.as_ptr()).collect::<Vec<_>>();
+    let device_extensions_ptrs = required_device_extensions.iter().map(|x| x.as_ptr())
+        .chain(physical_info.supported_optional_extensions.iter().map(|x| x.as_ptr()))
+        .collect::<Vec<_>>();
     let device_features = vk::PhysicalDeviceFeatures::builder().build();
     let device_create_info = vk::DeviceCreateInfo::builder()
         .queue_create_infos(&queue_create_infos)
@@ -129,23 +170,45 @@ fn create_logical_device(
 pub struct DeviceCtx {
     pub surface_ctx: Rc<SurfaceCtx>,
     pub physical_info: PhysicalDeviceInfo,
-    pub logical_info: LogicalDeviceInfo
+    pub logical_info: LogicalDeviceInfo,
+    pub debug_namer: DebugNamer
 }
 
 impl DeviceCtx {
+    /// `required_device_extensions` rules out any device that doesn't support
+    /// all of them. `optional_device_extensions` never rules a device out,
+    /// but devices supporting more of them score higher, and whichever of
+    /// them a device supports are enabled on the logical device and recorded
+    /// in [`PhysicalDeviceInfo::supported_optional_extensions`] so callers
+    /// can branch on hardware capability (e.g. ray tracing).
     pub fn new(
-        surface_ctx: Rc<SurfaceCtx>
+        surface_ctx: Rc<SurfaceCtx>,
+        required_device_extensions: &[CString],
+        optional_device_extensions: &[CString]
     ) -> Result<Rc<DeviceCtx>> {
-        let required_ext = get_required_device_extensions();
+        let required_ext = base_required_device_extensions().into_iter()
+            .chain(required_device_extensions.iter().cloned())
+            .collect::<Vec<_>>();
         let required_ext_ref = required_ext.iter().map(CString::as_c_str).collect::<Vec<_>>();
-        let physical_info = select_physical_device(&surface_ctx, &required_ext_ref)?;
+        let optional_ext_ref = optional_device_extensions.iter().map(CString::as_c_str).collect::<Vec<_>>();
+        let physical_info = select_physical_device(&surface_ctx, &required_ext_ref, &optional_ext_ref)?;
         let logical_info = create_logical_device(&surface_ctx, &physical_info, &surface_ctx.instance_ctx.layer_name_pointers, &required_ext_ref)?;
-        
+
         log::debug!("DeviceCtx created ({})", physical_info.debug_device_name.to_str().unwrap_or("vkw: device is not nameable"));
+        let debug_namer = DebugNamer::new(
+            &surface_ctx.instance_ctx.entry_ctx.entry,
+            &surface_ctx.instance_ctx.instance,
+            &logical_info.device,
+            surface_ctx.instance_ctx.enable_validation
+        );
+        debug_namer.name_object(logical_info.device.handle(), "rtrt::device")?;
+        debug_namer.name_object(logical_info.graphics_queue, "rtrt::graphics_queue")?;
+        debug_namer.name_object(logical_info.present_queue, "rtrt::present_queue")?;
         Ok(Rc::new(DeviceCtx {
             surface_ctx,
             physical_info,
-            logical_info
+            logical_info,
+            debug_namer
         }))
     }
 
@@ -161,6 +224,14 @@ impl DeviceCtx {
         unsafe { self.logical_info.device.device_wait_idle()? }
         Ok(())
     }
+
+    /// `VkPhysicalDeviceLimits::nonCoherentAtomSize`: the granularity
+    /// `vkFlushMappedMemoryRanges`/`vkInvalidateMappedMemoryRanges` require
+    /// `offset`/`size` to be aligned to for non-coherent host-visible memory.
+    pub fn non_coherent_atom_size(&self) -> vk::DeviceSize {
+        let props = unsafe { self.surface_ctx.instance_ctx.instance.get_physical_device_properties(self.physical_info.device) };
+        props.limits.non_coherent_atom_size
+    }
 }
 
 impl Drop for DeviceCtx {
@@ -177,7 +248,16 @@ pub struct PhysicalDeviceInfo {
     pub graphics_family_index: u32,
     pub present_family_index: u32,
     pub dedup_family_indices: Vec<u32>,
-    pub debug_device_name: CString
+    pub debug_device_name: CString,
+    /// Which of the optional extensions passed to `DeviceCtx::new` this
+    /// device actually supports (and has had enabled on the logical device).
+    pub supported_optional_extensions: Vec<CString>
+}
+
+impl PhysicalDeviceInfo {
+    pub fn supports_extension(&self, name: &CStr) -> bool {
+        self.supported_optional_extensions.iter().any(|x| x.as_c_str() == name)
+    }
 }
 
 pub struct LogicalDeviceInfo {
@@ -0,0 +1,19 @@
+use std::rc::Rc;
+
+use anyhow::Result;
+use ash::Entry;
+
+pub struct EntryCtx {
+    pub entry: Entry
+}
+
+impl EntryCtx {
+    pub fn new() -> Result<Rc<EntryCtx>> {
+        let entry = unsafe { Entry::load()? };
+
+        log::debug!("EntryCtx created");
+        Ok(Rc::new(EntryCtx {
+            entry
+        }))
+    }
+}
@@ -0,0 +1,171 @@
+use std::{cell::{Cell, RefCell}, rc::Rc};
+
+use anyhow::Result;
+use ash::vk::{self, Fence, Semaphore};
+
+use super::{device::DeviceCtx, render_chain::is_out_of_date, semaphore::{create_fence, create_semaphore}, swapchain::SwapchainCtx};
+
+pub const MAX_FRAMES_IN_FLIGHT: usize = 2;
+
+/// What the caller needs to record and submit a frame's commands, handed
+/// back by [`FrameSyncCtx::begin_frame`].
+pub struct FrameAcquire {
+    pub image_index: u32,
+    pub image_available_semaphore: Semaphore,
+    pub render_finished_semaphore: Semaphore
+}
+
+/// Ties the per-frame semaphores and fences together into a double/triple
+/// buffered render loop, so the CPU can record frame N+1 while the GPU is
+/// still working on frame N instead of stalling every frame.
+pub struct FrameSyncCtx {
+    device_ctx: Rc<DeviceCtx>,
+    image_available_semaphores: Vec<Semaphore>,
+    render_finished_semaphores: Vec<Semaphore>,
+    in_flight_fences: Vec<Fence>,
+    /// Indexed by swapchain image, not by frame-in-flight slot: tracks which
+    /// in-flight fence (if any) is still using a given swapchain image, in
+    /// case the frames-in-flight count and the swapchain image count differ.
+    images_in_flight: RefCell<Vec<Fence>>,
+    current_frame: Cell<usize>
+}
+
+impl FrameSyncCtx {
+    pub fn new(device_ctx: Rc<DeviceCtx>, swapchain_image_count: usize) -> Result<Rc<FrameSyncCtx>> {
+        let mut image_available_semaphores = Vec::with_capacity(MAX_FRAMES_IN_FLIGHT);
+        let mut render_finished_semaphores = Vec::with_capacity(MAX_FRAMES_IN_FLIGHT);
+        let mut in_flight_fences = Vec::with_capacity(MAX_FRAMES_IN_FLIGHT);
+        for i in 0..MAX_FRAMES_IN_FLIGHT {
+            image_available_semaphores.push(create_semaphore(&device_ctx, &format!("rtrt::image_available_semaphore[{i}]"))?);
+            render_finished_semaphores.push(create_semaphore(&device_ctx, &format!("rtrt::render_finished_semaphore[{i}]"))?);
+            in_flight_fences.push(create_fence(&device_ctx, true, &format!("rtrt::in_flight_fence[{i}]"))?);
+        }
+        let images_in_flight = vec![vk::Fence::null(); swapchain_image_count];
+
+        log::debug!("FrameSyncCtx created ({MAX_FRAMES_IN_FLIGHT} frames in flight)");
+        Ok(Rc::new(FrameSyncCtx {
+            device_ctx,
+            image_available_semaphores,
+            render_finished_semaphores,
+            in_flight_fences,
+            images_in_flight: RefCell::new(images_in_flight),
+            current_frame: Cell::new(0)
+        }))
+    }
+
+    /// Rebuilds `images_in_flight` to the new swapchain's image count. Must
+    /// be called after `RenderChainCtx::recreate`, since that can change how
+    /// many images the swapchain has, which would otherwise leave
+    /// `images_in_flight` either too small (so `begin_frame` indexes out of
+    /// bounds) or stale (holding fences for images that no longer exist).
+    pub fn resize(&self, swapchain_image_count: usize) {
+        self.images_in_flight.replace(vec![vk::Fence::null(); swapchain_image_count]);
+    }
+
+    /// Waits for the current frame slot to be free, acquires the next
+    /// swapchain image (waiting for whichever fence last used it, if any),
+    /// and returns the semaphores the caller should submit/present with.
+    /// Returns `Ok(None)` if the swapchain is out of date and should be
+    /// recreated via `RenderChainCtx::recreate` before trying again.
+    pub fn begin_frame(&self, swapchain_ctx: &SwapchainCtx) -> Result<Option<FrameAcquire>> {
+        let device = &self.device_ctx.logical_info.device;
+        let frame = self.current_frame.get();
+        let in_flight_fence = self.in_flight_fences[frame];
+        unsafe { device.wait_for_fences(&[in_flight_fence], true, u64::MAX)?; }
+
+        let image_available_semaphore = self.image_available_semaphores[frame];
+        let acquire_result = unsafe {
+            swapchain_ctx.swapchain_loader.acquire_next_image(swapchain_ctx.swapchain_khr, u64::MAX, image_available_semaphore, vk::Fence::null())
+        };
+        // `acquire_next_image` has already scheduled a signal on
+        // `image_available_semaphore` by the time it returns `Ok`, even when
+        // `suboptimal` is `true` - so that case must still be submitted and
+        // presented (end_frame reports suboptimal/out-of-date from the
+        // present call) rather than bailing here, or the semaphore would be
+        // re-signalled before a wait ever consumed its first signal. Only
+        // the `Err` path is safe to skip, since no signal was scheduled.
+        let (image_index, _suboptimal) = match acquire_result {
+            Ok(result) => result,
+            Err(e) if is_out_of_date(e) => return Ok(None),
+            Err(e) => return Err(e.into())
+        };
+
+        let mut images_in_flight = self.images_in_flight.borrow_mut();
+        let image_fence = images_in_flight[image_index as usize];
+        if image_fence != vk::Fence::null() {
+            unsafe { device.wait_for_fences(&[image_fence], true, u64::MAX)?; }
+        }
+        images_in_flight[image_index as usize] = in_flight_fence;
+
+        Ok(Some(FrameAcquire {
+            image_index,
+            image_available_semaphore,
+            render_finished_semaphore: self.render_finished_semaphores[frame]
+        }))
+    }
+
+    /// Submits `command_buffer` to `graphics_queue` (waiting on the acquire
+    /// semaphore, signalling the render-finished semaphore and the frame's
+    /// fence), then presents `image_index` on `present_queue`. Returns `true`
+    /// if the caller should recreate the swapchain afterwards.
+    pub fn end_frame(
+        &self,
+        swapchain_ctx: &SwapchainCtx,
+        acquire: &FrameAcquire,
+        command_buffer: vk::CommandBuffer,
+        graphics_queue: vk::Queue,
+        present_queue: vk::Queue
+    ) -> Result<bool> {
+        let device = &self.device_ctx.logical_info.device;
+        let frame = self.current_frame.get();
+        let in_flight_fence = self.in_flight_fences[frame];
+
+        let wait_semaphores = [acquire.image_available_semaphore];
+        let wait_stages = [vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT];
+        let signal_semaphores = [acquire.render_finished_semaphore];
+        let command_buffers = [command_buffer];
+        let submit_info = vk::SubmitInfo::builder()
+            .wait_semaphores(&wait_semaphores)
+            .wait_dst_stage_mask(&wait_stages)
+            .command_buffers(&command_buffers)
+            .signal_semaphores(&signal_semaphores)
+            .build();
+
+        unsafe {
+            device.reset_fences(&[in_flight_fence])?;
+            device.queue_submit(graphics_queue, &[submit_info], in_flight_fence)?;
+        }
+
+        let swapchains = [swapchain_ctx.swapchain_khr];
+        let image_indices = [acquire.image_index];
+        let present_info = vk::PresentInfoKHR::builder()
+            .wait_semaphores(&signal_semaphores)
+            .swapchains(&swapchains)
+            .image_indices(&image_indices)
+            .build();
+        let present_result = unsafe { swapchain_ctx.swapchain_loader.queue_present(present_queue, &present_info) };
+        let needs_recreate = match present_result {
+            Ok(suboptimal) => suboptimal,
+            Err(e) if is_out_of_date(e) => true,
+            Err(e) => return Err(e.into())
+        };
+
+        self.current_frame.set((frame + 1) % MAX_FRAMES_IN_FLIGHT);
+        Ok(needs_recreate)
+    }
+}
+
+impl Drop for FrameSyncCtx {
+    fn drop(&mut self) {
+        unsafe {
+            let device = &self.device_ctx.logical_info.device;
+            for &semaphore in self.image_available_semaphores.iter().chain(&self.render_finished_semaphores) {
+                device.destroy_semaphore(semaphore, None);
+            }
+            for &fence in &self.in_flight_fences {
+                device.destroy_fence(fence, None);
+            }
+        }
+        log::debug!("FrameSyncCtx dropped");
+    }
+}
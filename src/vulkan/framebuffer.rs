@@ -0,0 +1,54 @@
+use std::rc::Rc;
+
+use anyhow::Result;
+use ash::vk::{self, Framebuffer};
+
+use super::render_pass::RenderPassCtx;
+
+pub struct FramebufferCtx {
+    pub render_pass_ctx: Rc<RenderPassCtx>,
+    pub framebuffers: Vec<Framebuffer>
+}
+
+impl FramebufferCtx {
+    pub fn new(
+        render_pass_ctx: Rc<RenderPassCtx>
+    ) -> Result<Rc<FramebufferCtx>> {
+        let swapchain_ctx = &render_pass_ctx.swapchain_ctx;
+        let device = &swapchain_ctx.device_ctx.logical_info.device;
+        let framebuffers = swapchain_ctx.swapchain_image_views.iter()
+            .enumerate()
+            .map(|(index, &image_view)| {
+                let attachments = [image_view];
+                let framebuffer_info = vk::FramebufferCreateInfo::builder()
+                    .render_pass(render_pass_ctx.render_pass)
+                    .attachments(&attachments)
+                    .width(swapchain_ctx.swapchain_extent.width)
+                    .height(swapchain_ctx.swapchain_extent.height)
+                    .layers(1)
+                    .build();
+                let framebuffer = unsafe { device.create_framebuffer(&framebuffer_info, None)? };
+                swapchain_ctx.device_ctx.debug_namer.name_object(framebuffer, &format!("rtrt::framebuffer[{index}]"))?;
+                Ok(framebuffer)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        log::debug!("FramebufferCtx created ({} framebuffers)", framebuffers.len());
+        Ok(Rc::new(FramebufferCtx {
+            render_pass_ctx,
+            framebuffers
+        }))
+    }
+}
+
+impl Drop for FramebufferCtx {
+    fn drop(&mut self) {
+        unsafe {
+            let device = &self.render_pass_ctx.swapchain_ctx.device_ctx.logical_info.device;
+            for &framebuffer in &self.framebuffers {
+                device.destroy_framebuffer(framebuffer, None);
+            }
+        }
+        log::debug!("FramebufferCtx dropped");
+    }
+}
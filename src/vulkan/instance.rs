@@ -0,0 +1,87 @@
+use std::{ffi::{CString, c_char, CStr}, rc::Rc};
+
+use anyhow::Result;
+use ash::{vk, Instance};
+use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
+
+use super::{debug::DebugMessengerCtx, entry::EntryCtx};
+
+const VALIDATION_LAYER_NAME: &CStr = unsafe {
+    CStr::from_bytes_with_nul_unchecked(b"VK_LAYER_KHRONOS_validation\0")
+};
+
+fn get_layer_names(enable_validation: bool) -> Vec<CString> {
+    if enable_validation {
+        vec![VALIDATION_LAYER_NAME.to_owned()]
+    } else {
+        vec![]
+    }
+}
+
+pub struct InstanceCtx {
+    pub entry_ctx: Rc<EntryCtx>,
+    pub instance: Instance,
+    pub enable_validation: bool,
+    pub layer_names: Vec<CString>,
+    pub layer_name_pointers: Vec<*const c_char>,
+    /// `None` whenever `enable_validation` is `false`.
+    pub debug_messenger: Option<DebugMessengerCtx>
+}
+
+impl InstanceCtx {
+    pub fn new(
+        entry_ctx: Rc<EntryCtx>,
+        window: &(impl HasRawWindowHandle + HasRawDisplayHandle),
+        enable_validation: bool
+    ) -> Result<Rc<InstanceCtx>> {
+        let app_name = CString::new("rtrt")?;
+        let engine_name = CString::new("rtrt")?;
+        let app_info = vk::ApplicationInfo::builder()
+            .application_name(&app_name)
+            .application_version(vk::make_api_version(0, 0, 1, 0))
+            .engine_name(&engine_name)
+            .engine_version(vk::make_api_version(0, 0, 1, 0))
+            .api_version(vk::API_VERSION_1_2)
+            .build();
+
+        let layer_names = get_layer_names(enable_validation);
+        let layer_name_pointers = layer_names.iter().map(|x| x.as_ptr()).collect::<Vec<_>>();
+
+        let mut extension_pointers = ash_window::enumerate_required_extensions(window.raw_display_handle())?
+            .to_vec();
+        if enable_validation {
+            extension_pointers.push(ash::extensions::ext::DebugUtils::name().as_ptr());
+        }
+
+        let instance_create_info = vk::InstanceCreateInfo::builder()
+            .application_info(&app_info)
+            .enabled_layer_names(&layer_name_pointers)
+            .enabled_extension_names(&extension_pointers)
+            .build();
+
+        let instance = unsafe { entry_ctx.entry.create_instance(&instance_create_info, None)? };
+        let debug_messenger = enable_validation.then(|| DebugMessengerCtx::new(&entry_ctx.entry, &instance)).transpose()?;
+
+        log::debug!("InstanceCtx created");
+        Ok(Rc::new(InstanceCtx {
+            entry_ctx,
+            instance,
+            enable_validation,
+            layer_names,
+            layer_name_pointers,
+            debug_messenger
+        }))
+    }
+}
+
+impl Drop for InstanceCtx {
+    fn drop(&mut self) {
+        // The messenger must be destroyed while the instance it was
+        // registered against is still alive.
+        self.debug_messenger.take();
+        unsafe {
+            self.instance.destroy_instance(None);
+        }
+        log::debug!("InstanceCtx dropped");
+    }
+}
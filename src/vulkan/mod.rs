@@ -1,11 +1,15 @@
+pub mod allocator;
+pub mod buffer;
 pub mod command_buffers;
 pub mod command_pool;
 pub mod debug;
 pub mod device;
 pub mod entry;
 pub mod framebuffer;
+pub mod frame_sync;
 pub mod instance;
 pub mod pipeline;
+pub mod render_chain;
 pub mod render_pass;
 pub mod semaphore;
 pub mod shader;
@@ -0,0 +1,181 @@
+use std::{cell::RefCell, ffi::CString, rc::Rc};
+
+use anyhow::Result;
+use ash::vk::{self, Pipeline, PipelineLayout};
+
+use super::{render_pass::RenderPassCtx, shader::ShaderCtx};
+
+/// A graphics pipeline built from a vertex and fragment `ShaderCtx`. The
+/// pipeline handle itself sits behind a `RefCell` so [`GraphicsPipelineCtx::poll_reload`]
+/// can rebuild it in place when shader hot-reload (chunk0-6) swaps either
+/// shader's `vkShaderModule` out from under it, letting callers keep a
+/// stable `Rc<GraphicsPipelineCtx>` across a reload instead of re-wiring it.
+pub struct GraphicsPipelineCtx {
+    pub render_pass_ctx: Rc<RenderPassCtx>,
+    vertex_shader_ctx: Rc<ShaderCtx>,
+    vertex_entry_point: CString,
+    fragment_shader_ctx: Rc<ShaderCtx>,
+    fragment_entry_point: CString,
+    pub pipeline_layout: PipelineLayout,
+    pipeline: RefCell<Pipeline>
+}
+
+impl GraphicsPipelineCtx {
+    pub fn new(
+        render_pass_ctx: Rc<RenderPassCtx>,
+        vertex_shader_ctx: Rc<ShaderCtx>,
+        vertex_entry_point: &str,
+        fragment_shader_ctx: Rc<ShaderCtx>,
+        fragment_entry_point: &str
+    ) -> Result<Rc<GraphicsPipelineCtx>> {
+        let device = &render_pass_ctx.swapchain_ctx.device_ctx.logical_info.device;
+
+        let layout_info = vk::PipelineLayoutCreateInfo::builder().build();
+        let pipeline_layout = unsafe { device.create_pipeline_layout(&layout_info, None)? };
+        render_pass_ctx.swapchain_ctx.device_ctx.debug_namer.name_object(pipeline_layout, "rtrt::pipeline_layout")?;
+
+        let vertex_entry_point = CString::new(vertex_entry_point)?;
+        let fragment_entry_point = CString::new(fragment_entry_point)?;
+        let pipeline = build_pipeline(
+            &render_pass_ctx,
+            pipeline_layout,
+            &vertex_shader_ctx,
+            &vertex_entry_point,
+            &fragment_shader_ctx,
+            &fragment_entry_point
+        )?;
+
+        log::debug!("GraphicsPipelineCtx created");
+        Ok(Rc::new(GraphicsPipelineCtx {
+            render_pass_ctx,
+            vertex_shader_ctx,
+            vertex_entry_point,
+            fragment_shader_ctx,
+            fragment_entry_point,
+            pipeline_layout,
+            pipeline: RefCell::new(pipeline)
+        }))
+    }
+
+    pub fn pipeline(&self) -> Pipeline {
+        *self.pipeline.borrow()
+    }
+
+    /// Polls both shaders for hot-reload (a no-op outside the
+    /// `shader-hot-reload` feature) and, if either reloaded, rebuilds this
+    /// pipeline from the new module(s) in place. Returns `true` if the
+    /// pipeline was rebuilt, so callers know to stop using any command
+    /// buffers recorded against the old handle.
+    pub fn poll_reload(&self) -> Result<bool> {
+        let vertex_reloaded = self.vertex_shader_ctx.reload_if_changed()?;
+        let fragment_reloaded = self.fragment_shader_ctx.reload_if_changed()?;
+        if !vertex_reloaded && !fragment_reloaded {
+            return Ok(false);
+        }
+
+        let new_pipeline = build_pipeline(
+            &self.render_pass_ctx,
+            self.pipeline_layout,
+            &self.vertex_shader_ctx,
+            &self.vertex_entry_point,
+            &self.fragment_shader_ctx,
+            &self.fragment_entry_point
+        )?;
+        let old_pipeline = self.pipeline.replace(new_pipeline);
+        unsafe {
+            self.render_pass_ctx.swapchain_ctx.device_ctx.logical_info.device.destroy_pipeline(old_pipeline, None);
+        }
+        log::debug!("GraphicsPipelineCtx rebuilt after shader reload");
+        Ok(true)
+    }
+}
+
+fn build_pipeline(
+    render_pass_ctx: &RenderPassCtx,
+    pipeline_layout: PipelineLayout,
+    vertex_shader_ctx: &ShaderCtx,
+    vertex_entry_point: &CString,
+    fragment_shader_ctx: &ShaderCtx,
+    fragment_entry_point: &CString
+) -> Result<Pipeline> {
+    let device = &render_pass_ctx.swapchain_ctx.device_ctx.logical_info.device;
+
+    let stages = [
+        vk::PipelineShaderStageCreateInfo::builder()
+            .stage(vk::ShaderStageFlags::VERTEX)
+            .module(vertex_shader_ctx.module())
+            .name(vertex_entry_point)
+            .build(),
+        vk::PipelineShaderStageCreateInfo::builder()
+            .stage(vk::ShaderStageFlags::FRAGMENT)
+            .module(fragment_shader_ctx.module())
+            .name(fragment_entry_point)
+            .build()
+    ];
+
+    let vertex_input_info = vk::PipelineVertexInputStateCreateInfo::builder().build();
+    let input_assembly_info = vk::PipelineInputAssemblyStateCreateInfo::builder()
+        .topology(vk::PrimitiveTopology::TRIANGLE_LIST)
+        .build();
+
+    let extent = render_pass_ctx.swapchain_ctx.swapchain_extent;
+    let viewports = [vk::Viewport::builder()
+        .width(extent.width as f32)
+        .height(extent.height as f32)
+        .max_depth(1.0)
+        .build()];
+    let scissors = [vk::Rect2D::builder().extent(extent).build()];
+    let viewport_info = vk::PipelineViewportStateCreateInfo::builder()
+        .viewports(&viewports)
+        .scissors(&scissors)
+        .build();
+
+    let rasterizer_info = vk::PipelineRasterizationStateCreateInfo::builder()
+        .polygon_mode(vk::PolygonMode::FILL)
+        .line_width(1.0)
+        .cull_mode(vk::CullModeFlags::BACK)
+        .front_face(vk::FrontFace::CLOCKWISE)
+        .build();
+
+    let multisample_info = vk::PipelineMultisampleStateCreateInfo::builder()
+        .rasterization_samples(vk::SampleCountFlags::TYPE_1)
+        .build();
+
+    let color_blend_attachments = [vk::PipelineColorBlendAttachmentState::builder()
+        .color_write_mask(vk::ColorComponentFlags::RGBA)
+        .build()];
+    let color_blend_info = vk::PipelineColorBlendStateCreateInfo::builder()
+        .attachments(&color_blend_attachments)
+        .build();
+
+    let pipeline_info = vk::GraphicsPipelineCreateInfo::builder()
+        .stages(&stages)
+        .vertex_input_state(&vertex_input_info)
+        .input_assembly_state(&input_assembly_info)
+        .viewport_state(&viewport_info)
+        .rasterization_state(&rasterizer_info)
+        .multisample_state(&multisample_info)
+        .color_blend_state(&color_blend_info)
+        .layout(pipeline_layout)
+        .render_pass(render_pass_ctx.render_pass)
+        .subpass(0)
+        .build();
+
+    let pipeline = unsafe {
+        device.create_graphics_pipelines(vk::PipelineCache::null(), &[pipeline_info], None)
+            .map_err(|(_, e)| e)?[0]
+    };
+    render_pass_ctx.swapchain_ctx.device_ctx.debug_namer.name_object(pipeline, "rtrt::pipeline")?;
+    Ok(pipeline)
+}
+
+impl Drop for GraphicsPipelineCtx {
+    fn drop(&mut self) {
+        unsafe {
+            let device = &self.render_pass_ctx.swapchain_ctx.device_ctx.logical_info.device;
+            device.destroy_pipeline(*self.pipeline.borrow(), None);
+            device.destroy_pipeline_layout(self.pipeline_layout, None);
+        }
+        log::debug!("GraphicsPipelineCtx dropped");
+    }
+}
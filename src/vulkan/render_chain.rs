@@ -0,0 +1,98 @@
+use std::{cell::RefCell, rc::Rc};
+
+use anyhow::Result;
+use ash::vk::Extent2D;
+
+use super::{device::DeviceCtx, framebuffer::FramebufferCtx, render_pass::RenderPassCtx, swapchain::{SwapchainCtx, choose_extent}};
+
+/// Owns the subchain of contexts that must be torn down and rebuilt whenever
+/// the surface is resized or the swapchain is reported out of date, as
+/// opposed to the permanent device/surface chain that outlives them.
+pub struct RenderChainCtx {
+    pub device_ctx: Rc<DeviceCtx>,
+    swapchain_ctx: RefCell<Option<Rc<SwapchainCtx>>>,
+    render_pass_ctx: RefCell<Option<Rc<RenderPassCtx>>>,
+    framebuffer_ctx: RefCell<Option<Rc<FramebufferCtx>>>
+}
+
+impl RenderChainCtx {
+    pub fn new(
+        device_ctx: Rc<DeviceCtx>,
+        fallback_extent: Extent2D
+    ) -> Result<Rc<RenderChainCtx>> {
+        let (swapchain_ctx, render_pass_ctx, framebuffer_ctx) = build_chain(&device_ctx, fallback_extent)?;
+
+        log::debug!("RenderChainCtx created");
+        Ok(Rc::new(RenderChainCtx {
+            device_ctx,
+            swapchain_ctx: RefCell::new(Some(swapchain_ctx)),
+            render_pass_ctx: RefCell::new(Some(render_pass_ctx)),
+            framebuffer_ctx: RefCell::new(Some(framebuffer_ctx))
+        }))
+    }
+
+    pub fn swapchain_ctx(&self) -> Rc<SwapchainCtx> {
+        self.swapchain_ctx.borrow().clone().expect("RenderChainCtx used while torn down")
+    }
+
+    pub fn render_pass_ctx(&self) -> Rc<RenderPassCtx> {
+        self.render_pass_ctx.borrow().clone().expect("RenderChainCtx used while torn down")
+    }
+
+    pub fn framebuffer_ctx(&self) -> Rc<FramebufferCtx> {
+        self.framebuffer_ctx.borrow().clone().expect("RenderChainCtx used while torn down")
+    }
+
+    /// Tears down and rebuilds the swapchain-dependent chain, re-querying the
+    /// surface's current extent. Callers should invoke this whenever
+    /// `vkAcquireNextImageKHR`/`vkQueuePresentKHR` return
+    /// `ERROR_OUT_OF_DATE_KHR`/`SUBOPTIMAL_KHR`, or on a window resize event.
+    ///
+    /// Does nothing (leaving the existing chain, if any, in place) while the
+    /// surface has zero area, e.g. a minimized window - `vkCreateSwapchainKHR`
+    /// rejects a `0x0` extent, and a minimize is exactly when a resize event
+    /// would otherwise trigger this. A skipped call also returns `Ok(())`, so
+    /// its result does not tell it apart from a rebuild; callers should keep
+    /// calling `recreate` (e.g. once per frame, or on the next resize event)
+    /// until the surface has non-zero area again.
+    pub fn recreate(&self, fallback_extent: Extent2D) -> Result<()> {
+        let support = self.device_ctx.current_swapchain_support()?;
+        let extent = choose_extent(&support.capabilities, fallback_extent);
+        if extent.width == 0 || extent.height == 0 {
+            log::debug!("RenderChainCtx recreate skipped (zero-area surface)");
+            return Ok(());
+        }
+
+        self.device_ctx.wait_for_idle()?;
+
+        // Drop in dependency order: framebuffers -> render pass -> swapchain
+        // image views -> swapchain.
+        self.framebuffer_ctx.replace(None);
+        self.render_pass_ctx.replace(None);
+        self.swapchain_ctx.replace(None);
+
+        let (swapchain_ctx, render_pass_ctx, framebuffer_ctx) = build_chain(&self.device_ctx, fallback_extent)?;
+        self.swapchain_ctx.replace(Some(swapchain_ctx));
+        self.render_pass_ctx.replace(Some(render_pass_ctx));
+        self.framebuffer_ctx.replace(Some(framebuffer_ctx));
+
+        log::debug!("RenderChainCtx recreated");
+        Ok(())
+    }
+}
+
+fn build_chain(
+    device_ctx: &Rc<DeviceCtx>,
+    fallback_extent: Extent2D
+) -> Result<(Rc<SwapchainCtx>, Rc<RenderPassCtx>, Rc<FramebufferCtx>)> {
+    let swapchain_ctx = SwapchainCtx::new(device_ctx.clone(), fallback_extent)?;
+    let render_pass_ctx = RenderPassCtx::new(swapchain_ctx.clone())?;
+    let framebuffer_ctx = FramebufferCtx::new(render_pass_ctx.clone())?;
+    Ok((swapchain_ctx, render_pass_ctx, framebuffer_ctx))
+}
+
+/// Returns `true` if an acquire/present result code means the caller should
+/// trigger [`RenderChainCtx::recreate`] rather than propagate an error.
+pub fn is_out_of_date(result: ash::vk::Result) -> bool {
+    matches!(result, ash::vk::Result::ERROR_OUT_OF_DATE_KHR | ash::vk::Result::SUBOPTIMAL_KHR)
+}
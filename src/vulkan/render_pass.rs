@@ -39,7 +39,8 @@ impl RenderPassCtx {
             .build();
 
         let render_pass = unsafe { swapchain_ctx.device_ctx.logical_info.device.create_render_pass(&render_pass_info, None)? };
-        
+        swapchain_ctx.device_ctx.debug_namer.name_object(render_pass, "rtrt::render_pass")?;
+
         log::debug!("RenderPassCtx created");
         Ok(Rc::new(RenderPassCtx {
             swapchain_ctx,
@@ -0,0 +1,21 @@
+use anyhow::Result;
+use ash::vk::{self, Fence, Semaphore};
+
+use super::device::DeviceCtx;
+
+pub fn create_semaphore(device_ctx: &DeviceCtx, name: &str) -> Result<Semaphore> {
+    let semaphore_info = vk::SemaphoreCreateInfo::builder().build();
+    let semaphore = unsafe { device_ctx.logical_info.device.create_semaphore(&semaphore_info, None)? };
+    device_ctx.debug_namer.name_object(semaphore, name)?;
+    Ok(semaphore)
+}
+
+/// `signaled` should be `true` for fences a frame loop waits on before it has
+/// submitted anything, so the first wait doesn't block forever.
+pub fn create_fence(device_ctx: &DeviceCtx, signaled: bool, name: &str) -> Result<Fence> {
+    let flags = if signaled { vk::FenceCreateFlags::SIGNALED } else { vk::FenceCreateFlags::empty() };
+    let fence_info = vk::FenceCreateInfo::builder().flags(flags).build();
+    let fence = unsafe { device_ctx.logical_info.device.create_fence(&fence_info, None)? };
+    device_ctx.debug_namer.name_object(fence, name)?;
+    Ok(fence)
+}
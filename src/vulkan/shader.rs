@@ -0,0 +1,126 @@
+use std::{cell::RefCell, io::Cursor, path::{Path, PathBuf}, rc::Rc};
+
+use anyhow::Result;
+use ash::vk::ShaderModule;
+
+use super::device::DeviceCtx;
+
+/// Where `build.rs` told us it put the compiled SPIR-V, and which entry
+/// points it found inside it.
+fn compiled_spv_path() -> PathBuf {
+    PathBuf::from(env!("RTRT_SHADER_SPV_PATH"))
+}
+
+pub fn entry_points() -> Vec<String> {
+    env!("RTRT_SHADER_ENTRY_POINTS").split(',').map(str::to_owned).collect()
+}
+
+fn load_shader_module(device_ctx: &DeviceCtx, path: &Path) -> Result<ShaderModule> {
+    let bytes = std::fs::read(path)?;
+    let words = ash::util::read_spv(&mut Cursor::new(bytes))?;
+    let module_info = ash::vk::ShaderModuleCreateInfo::builder().code(&words).build();
+    let module = unsafe { device_ctx.logical_info.device.create_shader_module(&module_info, None)? };
+    device_ctx.debug_namer.name_object(module, &format!("rtrt::shader_module({})", path.display()))?;
+    Ok(module)
+}
+
+/// Holds the `vkShaderModule` compiled from the `shaders` rust-gpu crate.
+/// The module is behind a `RefCell` so that
+/// [`ShaderCtx::reload_if_changed`] can rebuild it in place under a debug
+/// feature flag, letting callers keep a stable `Rc<ShaderCtx>` across a
+/// shader iteration instead of re-wiring the owning pipeline every reload.
+pub struct ShaderCtx {
+    pub device_ctx: Rc<DeviceCtx>,
+    path: PathBuf,
+    module: RefCell<ShaderModule>,
+    #[cfg(feature = "shader-hot-reload")]
+    watcher: hot_reload::SpvWatcher
+}
+
+impl ShaderCtx {
+    pub fn new(device_ctx: Rc<DeviceCtx>) -> Result<Rc<ShaderCtx>> {
+        let path = compiled_spv_path();
+        let module = load_shader_module(&device_ctx, &path)?;
+
+        log::debug!("ShaderCtx created ({})", path.display());
+        Ok(Rc::new(ShaderCtx {
+            device_ctx,
+            #[cfg(feature = "shader-hot-reload")]
+            watcher: hot_reload::SpvWatcher::new(&path)?,
+            path,
+            module: RefCell::new(module)
+        }))
+    }
+
+    pub fn module(&self) -> ShaderModule {
+        *self.module.borrow()
+    }
+
+    /// No-op outside the `shader-hot-reload` feature. When enabled, checks
+    /// whether the watched `.spv` file has changed since the last call and,
+    /// if so, recreates the `vkShaderModule` in place. Callers that build a
+    /// pipeline from [`ShaderCtx::module`] should rebuild that pipeline too
+    /// whenever this returns `true`.
+    pub fn reload_if_changed(&self) -> Result<bool> {
+        #[cfg(feature = "shader-hot-reload")]
+        {
+            if !self.watcher.poll_changed() {
+                return Ok(false);
+            }
+            let new_module = load_shader_module(&self.device_ctx, &self.path)?;
+            let old_module = self.module.replace(new_module);
+            unsafe { self.device_ctx.logical_info.device.destroy_shader_module(old_module, None); }
+            log::debug!("ShaderCtx reloaded ({})", self.path.display());
+            Ok(true)
+        }
+        #[cfg(not(feature = "shader-hot-reload"))]
+        Ok(false)
+    }
+}
+
+impl Drop for ShaderCtx {
+    fn drop(&mut self) {
+        unsafe {
+            self.device_ctx.logical_info.device.destroy_shader_module(*self.module.borrow(), None);
+        }
+        log::debug!("ShaderCtx dropped");
+    }
+}
+
+#[cfg(feature = "shader-hot-reload")]
+mod hot_reload {
+    use std::{path::Path, sync::mpsc::{Receiver, channel}};
+
+    use anyhow::Result;
+    use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+    /// Watches the compiled `.spv` for changes on a background thread;
+    /// `poll_changed` is cheap and non-blocking, meant to be called once per
+    /// frame from the render loop.
+    pub struct SpvWatcher {
+        _watcher: RecommendedWatcher,
+        events: Receiver<notify::Result<notify::Event>>
+    }
+
+    impl SpvWatcher {
+        pub fn new(path: &Path) -> Result<SpvWatcher> {
+            let (tx, events) = channel();
+            let mut watcher = notify::recommended_watcher(tx)?;
+            watcher.watch(path, RecursiveMode::NonRecursive)?;
+            Ok(SpvWatcher { _watcher: watcher, events })
+        }
+
+        pub fn poll_changed(&self) -> bool {
+            let mut changed = false;
+            // `try_recv` (unlike `recv_timeout(Duration::ZERO)`, which can
+            // return `Timeout` without consuming a ready event) reliably
+            // drains every event already queued since the last poll.
+            while let Ok(result) = self.events.try_recv() {
+                if matches!(result, Ok(event) if event.kind.is_modify()) {
+                    changed = true;
+                }
+            }
+            changed
+        }
+    }
+}
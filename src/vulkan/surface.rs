@@ -0,0 +1,47 @@
+use std::rc::Rc;
+
+use anyhow::Result;
+use ash::{extensions::khr::Surface, vk::SurfaceKHR};
+use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
+
+use super::instance::InstanceCtx;
+
+pub struct SurfaceCtx {
+    pub instance_ctx: Rc<InstanceCtx>,
+    pub surface: Surface,
+    pub surface_khr: SurfaceKHR
+}
+
+impl SurfaceCtx {
+    pub fn new(
+        instance_ctx: Rc<InstanceCtx>,
+        window: &(impl HasRawWindowHandle + HasRawDisplayHandle)
+    ) -> Result<Rc<SurfaceCtx>> {
+        let surface = Surface::new(&instance_ctx.entry_ctx.entry, &instance_ctx.instance);
+        let surface_khr = unsafe {
+            ash_window::create_surface(
+                &instance_ctx.entry_ctx.entry,
+                &instance_ctx.instance,
+                window.raw_display_handle(),
+                window.raw_window_handle(),
+                None
+            )?
+        };
+
+        log::debug!("SurfaceCtx created");
+        Ok(Rc::new(SurfaceCtx {
+            instance_ctx,
+            surface,
+            surface_khr
+        }))
+    }
+}
+
+impl Drop for SurfaceCtx {
+    fn drop(&mut self) {
+        unsafe {
+            self.surface.destroy_surface(self.surface_khr, None);
+        }
+        log::debug!("SurfaceCtx dropped");
+    }
+}
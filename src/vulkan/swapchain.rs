@@ -0,0 +1,140 @@
+use std::rc::Rc;
+
+use anyhow::Result;
+use ash::{extensions::khr::Swapchain, vk::{self, Extent2D, Format, Image, ImageView, SwapchainKHR}};
+
+use super::device::DeviceCtx;
+
+fn choose_surface_format(formats: &[vk::SurfaceFormatKHR]) -> vk::SurfaceFormatKHR {
+    formats.iter()
+        .find(|f| f.format == Format::B8G8R8A8_SRGB && f.color_space == vk::ColorSpaceKHR::SRGB_NONLINEAR)
+        .copied()
+        .unwrap_or(formats[0])
+}
+
+fn choose_present_mode(present_modes: &[vk::PresentModeKHR]) -> vk::PresentModeKHR {
+    present_modes.iter()
+        .copied()
+        .find(|&mode| mode == vk::PresentModeKHR::MAILBOX)
+        .unwrap_or(vk::PresentModeKHR::FIFO)
+}
+
+/// Resolves the extent a swapchain built against `capabilities` would use,
+/// given the window's last known size as a fallback for platforms that
+/// report `current_extent` as `u32::MAX`. Exposed so callers can check for a
+/// zero-area surface (e.g. a minimized window) before attempting to rebuild
+/// the swapchain.
+pub fn choose_extent(capabilities: &vk::SurfaceCapabilitiesKHR, fallback_extent: Extent2D) -> Extent2D {
+    if capabilities.current_extent.width != u32::MAX {
+        capabilities.current_extent
+    } else {
+        Extent2D {
+            width: fallback_extent.width.clamp(capabilities.min_image_extent.width, capabilities.max_image_extent.width),
+            height: fallback_extent.height.clamp(capabilities.min_image_extent.height, capabilities.max_image_extent.height)
+        }
+    }
+}
+
+pub struct SwapchainCtx {
+    pub device_ctx: Rc<DeviceCtx>,
+    pub swapchain_loader: Swapchain,
+    pub swapchain_khr: SwapchainKHR,
+    pub swapchain_images: Vec<Image>,
+    pub swapchain_image_views: Vec<ImageView>,
+    pub swapchain_image_format: Format,
+    pub swapchain_extent: Extent2D
+}
+
+impl SwapchainCtx {
+    pub fn new(
+        device_ctx: Rc<DeviceCtx>,
+        fallback_extent: Extent2D
+    ) -> Result<Rc<SwapchainCtx>> {
+        let support = device_ctx.current_swapchain_support()?;
+        let surface_format = choose_surface_format(&support.formats);
+        let present_mode = choose_present_mode(&support.present_modes);
+        let extent = choose_extent(&support.capabilities, fallback_extent);
+
+        let mut image_count = support.capabilities.min_image_count + 1;
+        if support.capabilities.max_image_count > 0 {
+            image_count = image_count.min(support.capabilities.max_image_count);
+        }
+
+        let physical_info = &device_ctx.physical_info;
+        let indices = [physical_info.graphics_family_index, physical_info.present_family_index];
+        let (sharing_mode, family_indices): (_, &[u32]) = if physical_info.graphics_family_index == physical_info.present_family_index {
+            (vk::SharingMode::EXCLUSIVE, &[])
+        } else {
+            (vk::SharingMode::CONCURRENT, &indices)
+        };
+
+        let swapchain_create_info = vk::SwapchainCreateInfoKHR::builder()
+            .surface(device_ctx.surface_ctx.surface_khr)
+            .min_image_count(image_count)
+            .image_format(surface_format.format)
+            .image_color_space(surface_format.color_space)
+            .image_extent(extent)
+            .image_array_layers(1)
+            .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT)
+            .image_sharing_mode(sharing_mode)
+            .queue_family_indices(family_indices)
+            .pre_transform(support.capabilities.current_transform)
+            .composite_alpha(vk::CompositeAlphaFlagsKHR::OPAQUE)
+            .present_mode(present_mode)
+            .clipped(true)
+            .build();
+
+        let swapchain_loader = Swapchain::new(&device_ctx.surface_ctx.instance_ctx.instance, &device_ctx.logical_info.device);
+        let swapchain_khr = unsafe { swapchain_loader.create_swapchain(&swapchain_create_info, None)? };
+        device_ctx.debug_namer.name_object(swapchain_khr, "rtrt::swapchain")?;
+        let swapchain_images = unsafe { swapchain_loader.get_swapchain_images(swapchain_khr)? };
+        let swapchain_image_views = swapchain_images.iter()
+            .enumerate()
+            .map(|(index, &image)| {
+                let image_view = create_image_view(&device_ctx, image, surface_format.format)?;
+                device_ctx.debug_namer.name_object(image_view, &format!("rtrt::swapchain_image_view[{index}]"))?;
+                Ok(image_view)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        log::debug!("SwapchainCtx created ({}x{}, {} images)", extent.width, extent.height, swapchain_images.len());
+        Ok(Rc::new(SwapchainCtx {
+            device_ctx,
+            swapchain_loader,
+            swapchain_khr,
+            swapchain_images,
+            swapchain_image_views,
+            swapchain_image_format: surface_format.format,
+            swapchain_extent: extent
+        }))
+    }
+}
+
+fn create_image_view(device_ctx: &DeviceCtx, image: Image, format: Format) -> Result<ImageView> {
+    let subresource_range = vk::ImageSubresourceRange::builder()
+        .aspect_mask(vk::ImageAspectFlags::COLOR)
+        .base_mip_level(0)
+        .level_count(1)
+        .base_array_layer(0)
+        .layer_count(1)
+        .build();
+    let image_view_info = vk::ImageViewCreateInfo::builder()
+        .image(image)
+        .view_type(vk::ImageViewType::TYPE_2D)
+        .format(format)
+        .subresource_range(subresource_range)
+        .build();
+    Ok(unsafe { device_ctx.logical_info.device.create_image_view(&image_view_info, None)? })
+}
+
+impl Drop for SwapchainCtx {
+    fn drop(&mut self) {
+        unsafe {
+            for &image_view in &self.swapchain_image_views {
+                self.device_ctx.logical_info.device.destroy_image_view(image_view, None);
+            }
+            self.swapchain_loader.destroy_swapchain(self.swapchain_khr, None);
+        }
+        log::debug!("SwapchainCtx dropped");
+    }
+}